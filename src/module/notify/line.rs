@@ -0,0 +1,31 @@
+//! LINE Notify backend.
+//!
+//! Wraps the existing `send_line_notify_with_image` call behind the
+//! `Notifier` trait, so LINE becomes just one more enabled backend instead
+//! of the only sink a pilot can dispatch to.
+
+use super::{DetectionEvent, Notifier};
+use crate::module::util::{common::send_line_notify_with_image, init::RoktrackProperty};
+
+/// Dispatches detections to LINE Notify using the project's existing
+/// configuration and image-attach support.
+pub struct LineNotifier {
+    property: RoktrackProperty,
+}
+
+impl LineNotifier {
+    pub fn new(property: RoktrackProperty) -> Self {
+        Self { property }
+    }
+}
+
+impl Notifier for LineNotifier {
+    fn notify(&self, event: &DetectionEvent) -> Result<(), String> {
+        let msg = format!(
+            "{} detected (conf {:.2}).",
+            event.class_label, event.confidence
+        );
+        send_line_notify_with_image(&msg, &event.image_path, self.property.conf.clone())
+            .map_err(|e| format!("LINE notify failed: {e}"))
+    }
+}
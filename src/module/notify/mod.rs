@@ -0,0 +1,163 @@
+//! Pluggable notification backends.
+//!
+//! Pilots used to call `send_line_notify_with_image` directly, hardwiring
+//! LINE Notify as the only sink detections could be routed to. `Notifier`
+//! decouples "a detection happened" from "where it gets reported": each
+//! backend implements `notify`, and `NotifierRegistry` fans a
+//! `DetectionEvent` out to every registered backend on its own detached
+//! thread, logging (and otherwise swallowing) a per-backend failure so one
+//! dead or slow endpoint doesn't block the pilot loop that called
+//! `notify_all`.
+
+use std::sync::Arc;
+
+pub mod line;
+pub mod webhook;
+
+/// Everything a backend needs to report one detection.
+#[derive(Debug, Clone)]
+pub struct DetectionEvent {
+    pub class_label: String,
+    pub confidence: f32,
+    /// (x, y, w, h) bounding box, in the same units as `Detection`.
+    pub bbox: (i32, i32, i32, i32),
+    pub timestamp: u64,
+    pub image_path: String,
+}
+
+impl DetectionEvent {
+    pub fn new(
+        class_label: String,
+        confidence: f32,
+        bbox: (i32, i32, i32, i32),
+        timestamp: u64,
+        image_path: String,
+    ) -> Self {
+        Self {
+            class_label,
+            confidence,
+            bbox,
+            timestamp,
+            image_path,
+        }
+    }
+}
+
+/// A single notification sink. Implementations should report failure via
+/// `Err` rather than panicking, so `NotifierRegistry` can log it and keep
+/// dispatching to the other backends.
+pub trait Notifier: Send + Sync {
+    fn notify(&self, event: &DetectionEvent) -> Result<(), String>;
+}
+
+/// Holds every enabled `Notifier` backend and fans a `DetectionEvent` out to
+/// all of them concurrently.
+#[derive(Default)]
+pub struct NotifierRegistry {
+    backends: Vec<Arc<dyn Notifier>>,
+}
+
+impl NotifierRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a backend. Order doesn't matter: dispatch is concurrent.
+    pub fn register(&mut self, backend: Box<dyn Notifier>) {
+        self.backends.push(Arc::from(backend));
+    }
+
+    /// Dispatches `event` to every registered backend on its own detached
+    /// thread and returns immediately, without waiting for any of them to
+    /// finish. The crate has no async runtime, so this is fire-and-forget by
+    /// thread rather than by future; a backend that errors (or simply runs
+    /// long, e.g. `webhook::REQUEST_TIMEOUT`) is logged and otherwise
+    /// ignored, and never holds up the pilot loop that called this.
+    pub fn notify_all(&self, event: &DetectionEvent) {
+        for backend in &self.backends {
+            let backend = backend.clone();
+            let event = event.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = backend.notify(&event) {
+                    log::warn!("Notifier backend failed: {e}");
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    fn event() -> DetectionEvent {
+        DetectionEvent::new("deer".into(), 0.9, (0, 0, 10, 10), 1_000, "img.jpg".into())
+    }
+
+    struct FailingNotifier;
+    impl Notifier for FailingNotifier {
+        fn notify(&self, _event: &DetectionEvent) -> Result<(), String> {
+            Err("synthetic failure".into())
+        }
+    }
+
+    /// Signals via `tx` once `notify` has actually run, since dispatch is
+    /// now detached threads rather than something the test can join.
+    struct RecordingNotifier(mpsc::Sender<()>);
+    impl Notifier for RecordingNotifier {
+        fn notify(&self, _event: &DetectionEvent) -> Result<(), String> {
+            let _ = self.0.send(());
+            Ok(())
+        }
+    }
+
+    struct SlowNotifier;
+    impl Notifier for SlowNotifier {
+        fn notify(&self, _event: &DetectionEvent) -> Result<(), String> {
+            std::thread::sleep(Duration::from_millis(200));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn a_failing_backend_does_not_block_or_fail_the_others() {
+        let (tx, rx) = mpsc::channel();
+        let mut registry = NotifierRegistry::new();
+        registry.register(Box::new(FailingNotifier));
+        registry.register(Box::new(RecordingNotifier(tx)));
+
+        registry.notify_all(&event());
+
+        rx.recv_timeout(Duration::from_secs(1))
+            .expect("the other backend should still have run");
+    }
+
+    #[test]
+    fn every_registered_backend_is_dispatched_to() {
+        let (tx, rx) = mpsc::channel();
+        let mut registry = NotifierRegistry::new();
+        registry.register(Box::new(RecordingNotifier(tx.clone())));
+        registry.register(Box::new(RecordingNotifier(tx)));
+
+        registry.notify_all(&event());
+
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    }
+
+    #[test]
+    fn notify_all_returns_without_waiting_for_a_slow_backend() {
+        let mut registry = NotifierRegistry::new();
+        registry.register(Box::new(SlowNotifier));
+
+        let start = Instant::now();
+        registry.notify_all(&event());
+
+        assert!(
+            start.elapsed() < Duration::from_millis(200),
+            "notify_all should dispatch and return without waiting on backends"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Generic webhook notification backend.
+//!
+//! POSTs a multipart form to an arbitrary URL configured in
+//! `RoktrackProperty.conf`: a `payload` text field holds the detection as
+//! JSON, and an `image` part attaches the detection image when it can be
+//! read (otherwise the form is sent without it). This lets
+//! detections be routed to Discord/Slack/home-automation endpoints without
+//! the crate knowing anything about them, instead of being hardwired to
+//! LINE Notify.
+
+use std::time::Duration;
+
+use super::{DetectionEvent, Notifier};
+
+/// Upper bound on a single webhook POST, so a dead or non-responding
+/// endpoint doesn't leave its detached `notify_all` thread running forever.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Webhook backend configuration, read from `RoktrackProperty.conf`.
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub enabled: bool,
+    pub url: String,
+}
+
+/// Posts detections as a multipart form (a JSON `payload` field plus the
+/// detection image, when readable) to a configured webhook URL.
+pub struct WebhookNotifier {
+    config: WebhookConfig,
+    /// Built once in `new` rather than per `notify` call, same as
+    /// `LineNotifier` holding its config once instead of rebuilding state on
+    /// every notification.
+    client: reqwest::blocking::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: WebhookConfig) -> Self {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("failed to build webhook client");
+        Self { config, client }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, event: &DetectionEvent) -> Result<(), String> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let payload = serde_json::json!({
+            "class_label": event.class_label,
+            "confidence": event.confidence,
+            "bbox": {
+                "x": event.bbox.0,
+                "y": event.bbox.1,
+                "w": event.bbox.2,
+                "h": event.bbox.3,
+            },
+            "timestamp": event.timestamp,
+        });
+
+        let mut form =
+            reqwest::blocking::multipart::Form::new().text("payload", payload.to_string());
+        match std::fs::read(&event.image_path) {
+            Ok(bytes) => {
+                form = form.part(
+                    "image",
+                    reqwest::blocking::multipart::Part::bytes(bytes)
+                        .file_name(event.image_path.clone()),
+                );
+            }
+            Err(e) => {
+                log::warn!("Webhook: failed to read image {}: {e}", event.image_path);
+            }
+        }
+
+        self.client
+            .post(&self.config.url)
+            .multipart(form)
+            .send()
+            .map_err(|e| format!("webhook POST to {} failed: {e}", self.config.url))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//! Scheduled, time-windowed detection aggregation for monitoring pilots.
+//!
+//! Per-frame handlers such as `MonitorAnimal` used to fire on every frame and
+//! only debounce notifications with a single hardcoded interval. A
+//! `DetectionRunner` instead runs on a schedule: it maintains a sliding
+//! `[t_from, t_to]` cursor `detection_window` ms wide, advancing `t_to` by
+//! `detection_step` ms every tick, and aggregates every detection whose
+//! `shooting_end_time` falls inside the window into one event before
+//! dispatch. A detection sitting on the trailing edge of the window (i.e.
+//! its timestamp is still `>= t_to`) is held back rather than emitted early,
+//! so the same animal isn't reported twice across two consecutive windows.
+//!
+//! `ClassCooldowns` replaces the single global notification interval with a
+//! per-class one, so e.g. a deer and a boar detected together each get their
+//! own throttle instead of sharing one.
+
+use std::collections::HashMap;
+
+use crate::module::vision::detector::Detection;
+
+/// One aggregated detection window, ready to be dispatched as a single event.
+#[derive(Debug, Clone)]
+pub struct WindowedEvent {
+    pub t_from: u64,
+    pub t_to: u64,
+    pub detections: Vec<Detection>,
+}
+
+/// Accumulates detections into a sliding time window and only emits them once
+/// the window has fully closed.
+pub struct DetectionRunner {
+    detection_window: u64,
+    detection_step: u64,
+    t_to: u64,
+    pending: Vec<(u64, Detection)>,
+}
+
+impl DetectionRunner {
+    /// Creates a runner over `detection_window` ms wide windows, advancing
+    /// `detection_step` ms per tick. Both come from `RoktrackProperty`.
+    pub fn new(detection_window: u64, detection_step: u64) -> Self {
+        Self {
+            detection_window,
+            detection_step,
+            t_to: 0,
+            pending: vec![],
+        }
+    }
+
+    /// The window width this runner was built with, so a caller holding onto
+    /// one across config reloads can tell whether it's gone stale.
+    pub fn detection_window(&self) -> u64 {
+        self.detection_window
+    }
+
+    /// The step this runner was built with, so a caller holding onto one
+    /// across config reloads can tell whether it's gone stale.
+    pub fn detection_step(&self) -> u64 {
+        self.detection_step
+    }
+
+    /// Feeds one frame's detections into the runner, tagged with the
+    /// shooting end time they were captured at.
+    pub fn ingest(&mut self, shooting_end_time: u64, detections: &[Detection]) {
+        if self.t_to == 0 {
+            // Anchor the window to the first frame seen instead of replaying
+            // detections from the epoch.
+            self.t_to = shooting_end_time;
+        }
+        self.pending
+            .extend(detections.iter().cloned().map(|d| (shooting_end_time, d)));
+    }
+
+    /// Advances the window towards `now` and, once it has fully closed,
+    /// returns the aggregated event for it. Detections at or past `t_to`
+    /// are left pending for a later window rather than emitted now, so a
+    /// detection straddling the boundary isn't double-reported.
+    pub fn tick(&mut self, now: u64) -> Option<WindowedEvent> {
+        if self.t_to == 0 || now < self.t_to {
+            return None; // Window hasn't closed yet.
+        }
+        let t_to = self.t_to;
+        let t_from = t_to.saturating_sub(self.detection_window);
+        self.t_to += self.detection_step;
+
+        let (in_window, still_pending): (Vec<_>, Vec<_>) = self
+            .pending
+            .drain(..)
+            .partition(|(t, _)| *t >= t_from && *t < t_to);
+        self.pending = still_pending;
+
+        if in_window.is_empty() {
+            return None;
+        }
+        Some(WindowedEvent {
+            t_from,
+            t_to,
+            detections: in_window.into_iter().map(|(_, d)| d).collect(),
+        })
+    }
+}
+
+/// Per-class notification throttle, keyed by the raw detection class id
+/// (`Detection::cls`) so it doesn't depend on any particular class enum
+/// implementing `Hash`/`Eq`.
+#[derive(Default)]
+pub struct ClassCooldowns {
+    last_notified: HashMap<u32, u64>,
+}
+
+impl ClassCooldowns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true, and records `now`, if `cls` is due for a notification:
+    /// either it has never fired, or `cooldown_ms` has elapsed since the
+    /// last one.
+    pub fn due(&mut self, cls: u32, now: u64, cooldown_ms: u64) -> bool {
+        let due = self
+            .last_notified
+            .get(&cls)
+            .map(|last| last + cooldown_ms <= now)
+            .unwrap_or(true);
+        if due {
+            self.last_notified.insert(cls, now);
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn det(cls: u32) -> Detection {
+        Detection {
+            cls,
+            ..Detection::default()
+        }
+    }
+
+    #[test]
+    fn exposes_the_window_and_step_it_was_built_with() {
+        let runner = DetectionRunner::new(1_000, 500);
+        assert_eq!(runner.detection_window(), 1_000);
+        assert_eq!(runner.detection_step(), 500);
+    }
+
+    #[test]
+    fn tick_returns_none_before_first_window_closes() {
+        let mut runner = DetectionRunner::new(1_000, 500);
+        runner.ingest(100, &[det(1)]); // anchors t_to = 100
+        assert!(runner.tick(50).is_none());
+        assert!(runner.tick(99).is_none());
+    }
+
+    #[test]
+    fn detection_on_the_trailing_edge_is_held_back_one_window() {
+        let mut runner = DetectionRunner::new(1_000, 500);
+        runner.ingest(100, &[det(1)]); // anchors t_to = 100
+        // The anchor window's upper bound equals the detection's own
+        // timestamp, so it's excluded (`t < t_to`) and held back rather
+        // than reported on this, its first possible window.
+        assert!(runner.tick(100).is_none());
+        // It surfaces once a later window's upper bound has moved past it.
+        let event = runner.tick(600).unwrap();
+        assert_eq!(event.t_from, 0);
+        assert_eq!(event.t_to, 600);
+        assert_eq!(event.detections.len(), 1);
+        assert_eq!(event.detections[0].cls, 1);
+    }
+
+    #[test]
+    fn window_slides_and_excludes_detections_left_behind() {
+        let mut runner = DetectionRunner::new(1_000, 500);
+        runner.ingest(100, &[det(1)]); // anchors t_to = 100
+        runner.tick(100); // closes the (empty) anchor window, t_to -> 600
+        runner.ingest(600, &[det(2)]); // lands exactly on the new trailing edge
+        let first = runner.tick(600).unwrap(); // det(1) now qualifies, det(2) doesn't yet
+        assert_eq!(first.detections.len(), 1);
+        assert_eq!(first.detections[0].cls, 1);
+
+        // Next window is [100, 1_100): t_to has moved past 600, so det(2) qualifies.
+        let second = runner.tick(1_100).unwrap();
+        assert_eq!(second.t_from, 100);
+        assert_eq!(second.detections.len(), 1);
+        assert_eq!(second.detections[0].cls, 2);
+    }
+
+    #[test]
+    fn multiple_detections_in_one_window_are_aggregated() {
+        let mut runner = DetectionRunner::new(1_000, 500);
+        runner.ingest(100, &[det(1)]);
+        runner.ingest(300, &[det(2)]);
+        runner.tick(100); // closes the (empty) anchor window
+        let event = runner.tick(600).unwrap();
+        assert_eq!(event.detections.len(), 2);
+    }
+
+    #[test]
+    fn empty_window_yields_no_event() {
+        let mut runner = DetectionRunner::new(1_000, 500);
+        runner.ingest(100, &[]);
+        assert!(runner.tick(600).is_none());
+    }
+
+    #[test]
+    fn class_cooldown_blocks_until_elapsed_then_fires_again() {
+        let mut cooldowns = ClassCooldowns::new();
+        assert!(cooldowns.due(1, 0, 1_000));
+        assert!(!cooldowns.due(1, 999, 1_000));
+        assert!(cooldowns.due(1, 1_000, 1_000));
+    }
+
+    #[test]
+    fn class_cooldowns_are_independent_per_class() {
+        let mut cooldowns = ClassCooldowns::new();
+        assert!(cooldowns.due(1, 0, 1_000));
+        assert!(cooldowns.due(2, 0, 1_000));
+        assert!(!cooldowns.due(1, 500, 1_000));
+    }
+}
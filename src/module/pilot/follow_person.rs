@@ -8,17 +8,42 @@ use crate::module::{
     device::Chassis,
     device::Roktrack,
     pilot::base,
+    pilot::sched::SchedSignal,
     pilot::RoktrackState,
+    supervisor::{self, Supervisor},
+    thermal::{self, ThermalGovernor, ThermalThresholds},
     util::init::RoktrackProperty,
     vision::detector::{sort, Detection, FilterClass, RoktrackClasses},
     vision::{VisionMgmtCommand, VisualInfo},
 };
 
-pub struct FollowPerson {}
+/// Fixed backoff used while waiting for a static, non-blurred image, in lieu
+/// of per-pilot tuning. See `Scheduler`.
+const BLUR_WAIT_MS: u64 = 300;
+
+pub struct FollowPerson {
+    governor: ThermalGovernor,
+    /// Vision's supervisor handle, so `handle` can notice the vision
+    /// subsystem has been escalated (exceeded its restart budget) and react
+    /// instead of silently losing frames forever. `None` until
+    /// `with_vision_supervisor` is called by whatever wires this pilot up to
+    /// `RoktrackVision::supervisor`.
+    vision_supervisor: Option<Supervisor>,
+}
 
 impl FollowPerson {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            governor: ThermalGovernor::new(ThermalThresholds::default()),
+            vision_supervisor: None,
+        }
+    }
+
+    /// Wires in vision's supervisor handle so `handle` can react (stop +
+    /// speak a fault code) once vision has been escalated.
+    pub fn with_vision_supervisor(mut self, vision_supervisor: Supervisor) -> Self {
+        self.vision_supervisor = Some(vision_supervisor);
+        self
     }
 }
 
@@ -37,11 +62,30 @@ impl PilotHandler for FollowPerson {
         visual_info: &mut VisualInfo,
         tx: Sender<VisionMgmtCommand>,
         property: RoktrackProperty,
-    ) {
+    ) -> SchedSignal {
         log::debug!("Start FollowPerson Handle");
+
+        // Apply graded thermal throttling before the hard-stop cliff: downshift
+        // resolution, then widen the vision loop's inter-frame sleep, as pi_temp climbs.
+        let governor_action = thermal::apply(&mut self.governor, state.pi_temp, &tx);
+        // Surfaced so inspector telemetry and notifications can report the
+        // current throttle level instead of only the raw pi_temp.
+        state.thermal_level = governor_action.level;
+
         // Assess and handle system safety
-        let system_risk = match assess_system_risk(state, device) {
+        let vision_escalated = supervisor::vision_escalated(&self.vision_supervisor);
+        let system_risk = match assess_system_risk(
+            state,
+            device,
+            governor_action.hard_stop,
+            vision_escalated,
+        ) {
             Some(SystemRisk::StateOff) => Some(base::stop(device)),
+            Some(SystemRisk::VisionEscalated) => {
+                let res = base::stop(device);
+                device.speak("vision_escalated");
+                Some(res)
+            }
             Some(SystemRisk::HighTemp) => {
                 let res = base::stop(device);
                 device.speak("high_temp");
@@ -56,7 +100,7 @@ impl PilotHandler for FollowPerson {
         };
         if system_risk.is_some() {
             log::warn!("System Risk Exists. Continue.");
-            return; // Risk exists, continue
+            return SchedSignal::Normal; // Risk exists, continue
         }
 
         let mut detections = visual_info.detections.clone();
@@ -66,7 +110,11 @@ impl PilotHandler for FollowPerson {
                 < device.inner.clone().lock().unwrap().target_time + 300
         {
             log::debug!("Waiting for Static Image.");
-            return; // wait for next image
+            // Sleep instead of an early return, so the scheduler doesn't
+            // re-run us until the image is expected to be static again.
+            return SchedSignal::Sleep(
+                device.inner.clone().lock().unwrap().target_time + BLUR_WAIT_MS,
+            );
         }
 
         // Sort markers based on the current phase
@@ -86,24 +134,52 @@ impl PilotHandler for FollowPerson {
         log::info!("Action is {:?}", action);
 
         // Handle the current phase
-        let _ = match action {
-            Some(ActPhase::TurnCountExceeded) => base::halt(state, device, tx),
-            Some(ActPhase::TurnMarkerInvisible) => base::reset_ex_height(state, device),
-            Some(ActPhase::TurnMarkerFound) => base::set_new_target(state, device, marker),
-            Some(ActPhase::InvertPhase) => base::invert_phase(state, device),
-            Some(ActPhase::MissionComplete) => base::mission_complete(state, device),
-            Some(ActPhase::TurnKeep) => base::keep_turn(state, device, tx),
-            Some(ActPhase::Stand) => base::stand(state, tx),
-            Some(ActPhase::StartTurn) => base::start_turn(state, device),
+        let signal = match action {
+            Some(ActPhase::TurnCountExceeded) => {
+                let _ = base::halt(state, device, tx);
+                SchedSignal::Normal
+            }
+            Some(ActPhase::TurnMarkerInvisible) => {
+                let _ = base::reset_ex_height(state, device);
+                SchedSignal::Normal
+            }
+            Some(ActPhase::TurnMarkerFound) => {
+                let _ = base::set_new_target(state, device, marker);
+                SchedSignal::Normal
+            }
+            Some(ActPhase::InvertPhase) => {
+                let _ = base::invert_phase(state, device);
+                SchedSignal::Normal
+            }
+            Some(ActPhase::MissionComplete) => {
+                let _ = base::mission_complete(state, device);
+                SchedSignal::Done
+            }
+            Some(ActPhase::TurnKeep) => {
+                let _ = base::keep_turn(state, device, tx);
+                SchedSignal::Normal
+            }
+            Some(ActPhase::Stand) => {
+                let _ = base::stand(state, tx);
+                SchedSignal::Normal
+            }
+            Some(ActPhase::StartTurn) => {
+                let _ = base::start_turn(state, device);
+                SchedSignal::Normal
+            }
             Some(ActPhase::ReachMarker) => {
                 log::info!("Reach Marker pausing.");
                 device.inner.lock().unwrap().pause();
-                Ok(())
+                SchedSignal::Sleep(chrono::Utc::now().timestamp_millis() as u64 + BLUR_WAIT_MS)
+            }
+            Some(ActPhase::Proceed) => {
+                let _ = base::proceed(state, device, marker, tx);
+                SchedSignal::Normal
             }
-            Some(ActPhase::Proceed) => base::proceed(state, device, marker, tx),
-            None => Ok(()),
+            None => SchedSignal::Normal,
         };
         log::debug!("End FollowPerson Handle");
+        signal
     }
 }
 
@@ -112,15 +188,27 @@ impl PilotHandler for FollowPerson {
 #[derive(Debug, Clone)]
 enum SystemRisk {
     StateOff,
+    VisionEscalated,
     HighTemp,
     Bumped,
 }
-/// Identify system-related risks
+/// Identify system-related risks. `thermal_critical` comes from the
+/// `ThermalGovernor`, which hard-stops only at its top threshold; below
+/// that it throttles gradually instead of signaling a risk here.
+/// `vision_escalated` comes from vision's `Supervisor::any_escalated`, set
+/// once it has given up restarting the vision thread.
 ///
-fn assess_system_risk(state: &RoktrackState, device: &Roktrack) -> Option<SystemRisk> {
+fn assess_system_risk(
+    state: &RoktrackState,
+    device: &Roktrack,
+    thermal_critical: bool,
+    vision_escalated: bool,
+) -> Option<SystemRisk> {
     if !state.state {
         Some(SystemRisk::StateOff)
-    } else if state.pi_temp > 70.0 {
+    } else if vision_escalated {
+        Some(SystemRisk::VisionEscalated)
+    } else if thermal_critical {
         Some(SystemRisk::HighTemp)
     } else if device.inner.clone().lock().unwrap().bumper.switch.is_low() {
         Some(SystemRisk::Bumped)
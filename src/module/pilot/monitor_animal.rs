@@ -1,27 +1,65 @@
 //! Monitoring Animal Pilot
 
+use std::collections::HashMap;
 use std::sync::mpsc::Sender;
 
 use super::PilotHandler;
 use crate::module::{
     device::Roktrack,
+    notify::{line::LineNotifier, webhook::WebhookConfig, webhook::WebhookNotifier},
+    notify::{DetectionEvent, NotifierRegistry},
     pilot::base,
+    pilot::detection_runner::{ClassCooldowns, DetectionRunner},
+    pilot::sched::SchedSignal,
     pilot::RoktrackState,
-    util::{common::send_line_notify_with_image, init::RoktrackProperty},
+    supervisor::{self, Supervisor},
+    thermal::{self, ThermalGovernor, ThermalThresholds},
+    util::init::RoktrackProperty,
     vision::VisionMgmtCommand,
     vision::{detector::AnimalClasses, VisualInfo},
 };
 
+/// Fallback per-class notification cooldown, used when `RoktrackProperty`
+/// doesn't configure one for a given `AnimalClasses` variant.
+const DEFAULT_COOLDOWN_MS: u64 = 60_000;
+/// Fixed backoff used while waiting for a static, non-blurred image, in lieu
+/// of per-pilot tuning. See `Scheduler`.
+const BLUR_WAIT_MS: u64 = 300;
+
 pub struct MonitorAnimal {
-    last_detected_time: u64,
+    // Built lazily from `RoktrackProperty.conf.monitor` on the first `handle`
+    // call, since `new` isn't given a property to read the schedule from, and
+    // rebuilt whenever `ConfigWatcher` swaps in a property whose
+    // detection_window/detection_step no longer match it, so a hot-reloaded
+    // schedule actually takes effect instead of being stuck with whatever was
+    // configured at startup.
+    runner: Option<DetectionRunner>,
+    cooldowns: ClassCooldowns,
+    governor: ThermalGovernor,
+    /// Vision's supervisor handle, so `handle` can notice the vision
+    /// subsystem has been escalated (exceeded its restart budget) and react
+    /// instead of silently losing frames forever. `None` until
+    /// `with_vision_supervisor` is called by whatever wires this pilot up to
+    /// `RoktrackVision::supervisor`.
+    vision_supervisor: Option<Supervisor>,
 }
 
 impl MonitorAnimal {
     pub fn new() -> Self {
         Self {
-            last_detected_time: 0,
+            runner: None,
+            cooldowns: ClassCooldowns::new(),
+            governor: ThermalGovernor::new(ThermalThresholds::default()),
+            vision_supervisor: None,
         }
     }
+
+    /// Wires in vision's supervisor handle so `handle` can react (stop +
+    /// speak a fault code) once vision has been escalated.
+    pub fn with_vision_supervisor(mut self, vision_supervisor: Supervisor) -> Self {
+        self.vision_supervisor = Some(vision_supervisor);
+        self
+    }
 }
 
 impl Default for MonitorAnimal {
@@ -37,23 +75,38 @@ impl PilotHandler for MonitorAnimal {
         state: &mut RoktrackState,
         device: &mut Roktrack,
         visual_info: &mut VisualInfo,
-        _tx: Sender<VisionMgmtCommand>,
+        tx: Sender<VisionMgmtCommand>,
         property: RoktrackProperty,
-    ) {
+    ) -> SchedSignal {
         log::debug!("Start MonitorAnimal Handle");
+
+        // Apply graded thermal throttling before the hard-stop cliff: downshift
+        // resolution, then widen the vision loop's inter-frame sleep, as pi_temp climbs.
+        let governor_action = thermal::apply(&mut self.governor, state.pi_temp, &tx);
+        // Surfaced so inspector telemetry and notifications can report the
+        // current throttle level instead of only the raw pi_temp.
+        state.thermal_level = governor_action.level;
+
         // Assess and handle system safety
-        let system_risk = match assess_system_risk(state) {
-            Some(SystemRisk::StateOff) => Some(base::stop(device)),
-            Some(SystemRisk::HighTemp) => {
-                let res = base::stop(device);
-                device.speak("high_temp");
-                Some(res)
-            }
-            None => None,
-        };
+        let vision_escalated = supervisor::vision_escalated(&self.vision_supervisor);
+        let system_risk =
+            match assess_system_risk(state, governor_action.hard_stop, vision_escalated) {
+                Some(SystemRisk::StateOff) => Some(base::stop(device)),
+                Some(SystemRisk::VisionEscalated) => {
+                    let res = base::stop(device);
+                    device.speak("vision_escalated");
+                    Some(res)
+                }
+                Some(SystemRisk::HighTemp) => {
+                    let res = base::stop(device);
+                    device.speak("high_temp");
+                    Some(res)
+                }
+                None => None,
+            };
         if system_risk.is_some() {
             log::warn!("System Risk Exists. Continue.");
-            return; // Risk exists, continue
+            return SchedSignal::Normal; // Risk exists, continue
         }
 
         let detections = visual_info.detections.clone();
@@ -64,27 +117,90 @@ impl PilotHandler for MonitorAnimal {
                 < device.inner.clone().lock().unwrap().target_time + 300
         {
             log::debug!("Waiting for Static Image.");
-            return; // wait for next image
+            // Sleep instead of an early return, so the scheduler doesn't
+            // re-run us until the image is expected to be static again.
+            return SchedSignal::Sleep(
+                device.inner.clone().lock().unwrap().target_time + BLUR_WAIT_MS,
+            );
         }
 
-        // Check animal exist
-        if !detections.is_empty() {
-            log::warn!("Animal Detected!!");
-            device.speak("animal_detecting");
-            // Get now.
-            let utc = chrono::Utc::now();
-            if self.last_detected_time + 60000 < utc.timestamp_millis() as u64 {
-                log::info!("Interval time has elapsed. Re-detection is notified.");
-                self.last_detected_time = utc.timestamp_millis() as u64;
-                let msg = format!(
-                    "{:?} detected.",
-                    AnimalClasses::from_u32(detections.first().unwrap().cls)
-                        .expect("Unknown animal.")
-                );
-                let _ = send_line_notify_with_image(&msg, &property.path.img.last, property.conf);
+        // Lazily build the scheduled runner from the configured window/step on
+        // first use, and rebuild it whenever a config reload has changed
+        // either value out from under it — otherwise a hot-reloaded
+        // detection_window/detection_step would never take effect.
+        let stale = self
+            .runner
+            .as_ref()
+            .is_some_and(|r| {
+                r.detection_window() != property.conf.monitor.detection_window
+                    || r.detection_step() != property.conf.monitor.detection_step
+            });
+        if self.runner.is_none() || stale {
+            self.runner = Some(DetectionRunner::new(
+                property.conf.monitor.detection_window,
+                property.conf.monitor.detection_step,
+            ));
+        }
+        let runner = self.runner.as_mut().unwrap();
+        runner.ingest(visual_info.shooting_end_time, &detections);
+
+        // Only dispatch once the sliding window has fully closed, so a
+        // detection on the trailing edge isn't reported twice.
+        let event = match runner.tick(visual_info.shooting_end_time) {
+            Some(event) => event,
+            None => {
+                log::debug!("Detection window still open. Deferring.");
+                return SchedSignal::Normal;
+            }
+        };
+
+        // Aggregate by class so each distinct animal in the window gets one notification.
+        let mut counts_by_class: HashMap<u32, usize> = HashMap::new();
+        for det in &event.detections {
+            *counts_by_class.entry(det.cls).or_insert(0) += 1;
+        }
+
+        for (cls, count) in counts_by_class {
+            let Some(class) = AnimalClasses::from_u32(cls) else {
+                log::warn!("Unknown animal class id {cls}. Skipping notification.");
+                continue;
+            };
+            let cooldown_ms = property
+                .conf
+                .monitor
+                .cooldown_ms(class)
+                .unwrap_or(DEFAULT_COOLDOWN_MS);
+            if !self.cooldowns.due(cls, event.t_to, cooldown_ms) {
+                log::debug!("{class:?} still in cooldown. Skipping notification.");
+                continue;
             }
+            log::warn!("Animal Detected!! {class:?} x{count}");
+            device.speak("animal_detecting");
+
+            // Built fresh from `property` on every dispatch, rather than cached,
+            // so a hot-reloaded webhook URL/enabled flag takes effect on the
+            // very next notification instead of being stuck with whatever was
+            // configured when the first one fired. Dispatch is already gated
+            // behind `cooldowns.due` above, so this isn't on the per-frame hot
+            // path.
+            let mut notifiers = NotifierRegistry::new();
+            notifiers.register(Box::new(LineNotifier::new(property.clone())));
+            notifiers.register(Box::new(WebhookNotifier::new(WebhookConfig {
+                enabled: property.conf.notify.webhook.enabled,
+                url: property.conf.notify.webhook.url.clone(),
+            })));
+            let marker = event.detections.iter().find(|d| d.cls == cls);
+            let event_detection = DetectionEvent::new(
+                format!("{class:?}"),
+                marker.map(|d| d.conf).unwrap_or(0.0),
+                marker.map(|d| (d.x, d.y, d.w, d.h)).unwrap_or((0, 0, 0, 0)),
+                event.t_to,
+                property.path.img.last.clone(),
+            );
+            notifiers.notify_all(&event_detection);
         }
         log::debug!("End MonitorAnimal Handle");
+        SchedSignal::Normal
     }
 }
 
@@ -93,14 +209,25 @@ impl PilotHandler for MonitorAnimal {
 #[derive(Debug, Clone)]
 enum SystemRisk {
     StateOff,
+    VisionEscalated,
     HighTemp,
 }
-/// Identify system-related risks
+/// Identify system-related risks. `thermal_critical` comes from the
+/// `ThermalGovernor`, which hard-stops only at its top threshold; below
+/// that it throttles gradually instead of signaling a risk here.
+/// `vision_escalated` comes from vision's `Supervisor::any_escalated`, set
+/// once it has given up restarting the vision thread.
 ///
-fn assess_system_risk(state: &RoktrackState) -> Option<SystemRisk> {
+fn assess_system_risk(
+    state: &RoktrackState,
+    thermal_critical: bool,
+    vision_escalated: bool,
+) -> Option<SystemRisk> {
     if !state.state {
         Some(SystemRisk::StateOff)
-    } else if state.pi_temp > 70.0 {
+    } else if vision_escalated {
+        Some(SystemRisk::VisionEscalated)
+    } else if thermal_critical {
         Some(SystemRisk::HighTemp)
     } else {
         None
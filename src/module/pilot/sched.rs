@@ -0,0 +1,241 @@
+//! Cooperative pilot scheduler.
+//!
+//! `PilotHandler::handle` used to be invoked unconditionally once per image,
+//! and every branch (`base::keep_turn`, `base::proceed`, ...) ran to
+//! completion synchronously, so multi-step maneuvers couldn't pace
+//! themselves and the loop had no way to prioritize work. `handle` now
+//! returns a `SchedSignal` describing what should happen next, and
+//! `Scheduler` drives a pilot on a configurable `scheduler_interval`,
+//! tracking a per-pilot next-wake timestamp and only calling `handle` again
+//! once it's due. The early `return`s that followed the ad-hoc
+//! `is_turning()`/`target_time + 300` wait checks in
+//! `MonitorAnimal`/`FollowPerson` are now explicit `Sleep` returns.
+//!
+//! `Scheduler::step` collapses the "check `is_due`, call `handle`, `apply`
+//! the signal" sequence into the single call a per-pilot thread loop needs
+//! to make every tick instead of invoking `handle` unconditionally:
+//!
+//! ```ignore
+//! let mut scheduler = Scheduler::new(property.conf.pilot.scheduler_interval);
+//! loop {
+//!     let now = chrono::Utc::now().timestamp_millis() as u64;
+//!     scheduler.step(now, || pilot.handle(&mut state, &mut device, &mut visual_info, tx.clone(), property.clone()));
+//!     if scheduler.is_done() {
+//!         break;
+//!     }
+//! }
+//! ```
+//!
+//! `supervise` below is that loop: it owns the `Scheduler`, drives a pilot
+//! off a `Receiver<VisualInfo>` (the same channel `RoktrackVision::run` is
+//! given as its `tx`), and wraps the whole thing in `Supervisor::supervise`
+//! so a panic inside `PilotHandler::handle` restarts the pilot thread with
+//! backoff instead of killing it for good — the same treatment
+//! `RoktrackVision::run` already gives the vision thread. Constructing one
+//! `Supervisor` and `Receiver<VisualInfo>` per pilot and calling `supervise`
+//! with them is still the composition root's job, not this module's.
+
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::JoinHandle;
+
+use super::PilotHandler;
+use crate::module::{
+    device::Roktrack,
+    pilot::RoktrackState,
+    supervisor::Supervisor,
+    util::init::RoktrackProperty,
+    vision::{VisionMgmtCommand, VisualInfo},
+};
+
+/// What a pilot wants the scheduler to do after one `handle` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedSignal {
+    /// Re-run after `scheduler_interval`, the normal pace for this pilot.
+    Normal,
+    /// Don't call `handle` again until `until_ms` (epoch millis) has passed,
+    /// e.g. while waiting for a static, non-blurred image.
+    Sleep(u64),
+    /// Unlike `Normal`, becomes due again immediately (`next_wake = now`)
+    /// instead of waiting a full `scheduler_interval`, so a pilot that has
+    /// more to do this tick (e.g. mid-maneuver) isn't throttled to the same
+    /// cadence as one that's idling.
+    Yield,
+    /// Mission complete; stop scheduling this pilot.
+    Done,
+}
+
+/// Drives one pilot's `handle` calls on a schedule, honoring the
+/// `SchedSignal` it returns instead of calling it unconditionally every tick.
+pub struct Scheduler {
+    scheduler_interval: u64,
+    next_wake: u64,
+    done: bool,
+}
+
+impl Scheduler {
+    /// Creates a scheduler that re-runs a pilot every `scheduler_interval`
+    /// ms by default, unless the pilot asks to `Sleep` longer or signals `Done`.
+    pub fn new(scheduler_interval: u64) -> Self {
+        Self {
+            scheduler_interval,
+            next_wake: 0,
+            done: false,
+        }
+    }
+
+    /// Whether the pilot is due to run, given the current epoch millis.
+    pub fn is_due(&self, now: u64) -> bool {
+        !self.done && now >= self.next_wake
+    }
+
+    /// Whether the pilot has signaled `Done` and should no longer be scheduled.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Applies the signal `handle` just returned, scheduling the next call.
+    pub fn apply(&mut self, now: u64, signal: SchedSignal) {
+        match signal {
+            SchedSignal::Normal => self.next_wake = now + self.scheduler_interval,
+            SchedSignal::Sleep(until_ms) => self.next_wake = until_ms,
+            SchedSignal::Yield => self.next_wake = now,
+            SchedSignal::Done => self.done = true,
+        }
+    }
+
+    /// Runs one scheduling step at `now`: calls `handle` and applies the
+    /// `SchedSignal` it returns only if the pilot is due, otherwise does
+    /// nothing. The single call a per-pilot loop needs every tick instead of
+    /// separately checking `is_due` and calling `apply`.
+    pub fn step(&mut self, now: u64, handle: impl FnOnce() -> SchedSignal) {
+        if self.is_due(now) {
+            let signal = handle();
+            self.apply(now, signal);
+        }
+    }
+}
+
+/// Drives one `PilotHandler` on a `Scheduler`, fed by `visual_info_rx` (the
+/// receiving half of the same channel `RoktrackVision::run` sends
+/// `VisualInfo` over), and wraps the loop in `supervisor.supervise` so a
+/// panic inside `handle` restarts the pilot thread with backoff instead of
+/// ending it for good — the same treatment `RoktrackVision::run` already
+/// gives the vision loop. Ends (a clean exit, not a restart) once `pilot`
+/// returns `SchedSignal::Done` or `visual_info_rx` is disconnected.
+pub fn supervise<P>(
+    supervisor: &Supervisor,
+    group: &'static str,
+    id: &'static str,
+    pilot: P,
+    state: Arc<Mutex<RoktrackState>>,
+    device: Arc<Mutex<Roktrack>>,
+    visual_info_rx: Receiver<VisualInfo>,
+    tx: Sender<VisionMgmtCommand>,
+    property: RoktrackProperty,
+    scheduler_interval: u64,
+    on_escalate: impl Fn() + Send + 'static,
+) -> JoinHandle<()>
+where
+    P: PilotHandler + Send + 'static,
+{
+    let pilot = Arc::new(Mutex::new(pilot));
+    // Shared so the supervised body closure can be restarted (re-run) without
+    // consuming the receiver, same as `RoktrackVision::run` wrapping its `rx`.
+    let visual_info_rx = Arc::new(Mutex::new(visual_info_rx));
+    let heartbeat_supervisor = supervisor.clone();
+
+    let body = move || {
+        let mut scheduler = Scheduler::new(scheduler_interval);
+        loop {
+            let mut visual_info = match visual_info_rx.lock().unwrap().recv() {
+                Ok(visual_info) => visual_info,
+                Err(_) => {
+                    log::info!("Pilot `{id}`: vision channel closed, exiting.");
+                    return;
+                }
+            };
+            let now = chrono::Utc::now().timestamp_millis() as u64;
+            scheduler.step(now, || {
+                // A panic inside `handle` poisons these locks; recover them so
+                // a restarted body runs instead of re-panicking on the lock.
+                pilot.lock().unwrap_or_else(PoisonError::into_inner).handle(
+                    &mut state.lock().unwrap_or_else(PoisonError::into_inner),
+                    &mut device.lock().unwrap_or_else(PoisonError::into_inner),
+                    &mut visual_info,
+                    tx.clone(),
+                    property.clone(),
+                )
+            });
+            heartbeat_supervisor.heartbeat(id);
+            if scheduler.is_done() {
+                return;
+            }
+        }
+    };
+
+    supervisor.supervise(group, id, body, on_escalate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_immediately_before_any_signal_is_applied() {
+        let scheduler = Scheduler::new(100);
+        assert!(scheduler.is_due(0));
+    }
+
+    #[test]
+    fn normal_reschedules_after_the_interval() {
+        let mut scheduler = Scheduler::new(100);
+        scheduler.apply(1_000, SchedSignal::Normal);
+        assert!(!scheduler.is_due(1_050));
+        assert!(scheduler.is_due(1_100));
+    }
+
+    #[test]
+    fn yield_is_due_again_immediately_unlike_normal() {
+        let mut scheduler = Scheduler::new(100);
+        scheduler.apply(1_000, SchedSignal::Yield);
+        assert!(scheduler.is_due(1_000));
+        assert!(scheduler.is_due(1_050));
+    }
+
+    #[test]
+    fn sleep_wakes_at_the_given_timestamp_regardless_of_interval() {
+        let mut scheduler = Scheduler::new(100);
+        scheduler.apply(1_000, SchedSignal::Sleep(5_000));
+        assert!(!scheduler.is_due(4_999));
+        assert!(scheduler.is_due(5_000));
+    }
+
+    #[test]
+    fn done_stops_scheduling_for_good() {
+        let mut scheduler = Scheduler::new(100);
+        scheduler.apply(1_000, SchedSignal::Done);
+        assert!(scheduler.is_done());
+        assert!(!scheduler.is_due(u64::MAX));
+    }
+
+    #[test]
+    fn step_skips_handle_when_not_due() {
+        let mut scheduler = Scheduler::new(100);
+        scheduler.apply(0, SchedSignal::Normal); // next_wake = 100
+        let mut called = false;
+        scheduler.step(50, || {
+            called = true;
+            SchedSignal::Normal
+        });
+        assert!(!called);
+    }
+
+    #[test]
+    fn step_calls_handle_and_applies_its_signal_when_due() {
+        let mut scheduler = Scheduler::new(100);
+        scheduler.step(0, || SchedSignal::Sleep(5_000));
+        assert!(!scheduler.is_due(4_999));
+        assert!(scheduler.is_due(5_000));
+    }
+}
@@ -0,0 +1,264 @@
+//! Thermal governor with graded throttling.
+//!
+//! `assess_system_risk` in both pilots only knew two temperature states:
+//! fine, or a hard `HighTemp` stop above 70°C. `ThermalGovernor` adds graded
+//! responses before that cliff: above a first threshold it downshifts
+//! inference resolution (`VisionMgmtCommand::SwitchSz320`), above a second
+//! it widens the vision loop's inter-frame sleep to shed sustained CPU/GPU
+//! load and let the Pi cool, and only at the top threshold does it hard-stop
+//! the device. Hysteresis keeps it from oscillating right at a boundary: a
+//! level is only eased back off once the temperature has dropped
+//! `hysteresis_c` below the threshold that raised it.
+
+use std::sync::mpsc::Sender;
+
+use super::vision::VisionMgmtCommand;
+
+/// Graded throttle level, ordered from coolest to hottest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThermalLevel {
+    Nominal,
+    Downshifted,
+    Throttled,
+    Critical,
+}
+
+/// Threshold configuration for the governor, in degrees Celsius.
+#[derive(Debug, Clone)]
+pub struct ThermalThresholds {
+    pub downshift_c: f32,
+    pub throttle_c: f32,
+    pub critical_c: f32,
+    /// Degrees below a threshold required before easing back off it.
+    pub hysteresis_c: f32,
+    /// Inter-frame sleep applied once `throttle_c` is crossed, in ms.
+    pub throttled_frame_sleep_ms: u64,
+}
+
+impl Default for ThermalThresholds {
+    fn default() -> Self {
+        Self {
+            downshift_c: 60.0,
+            throttle_c: 65.0,
+            critical_c: 70.0,
+            hysteresis_c: 3.0,
+            throttled_frame_sleep_ms: 50,
+        }
+    }
+}
+
+/// Inter-frame sleep used below `throttle_c`, matching the vision loop's
+/// original fixed interval.
+const NOMINAL_FRAME_SLEEP_MS: u64 = 10;
+
+/// What the governor wants applied this tick.
+#[derive(Debug, Clone)]
+pub struct GovernorAction {
+    pub level: ThermalLevel,
+    /// Only set when the level just changed, so callers don't re-send the
+    /// same `VisionMgmtCommand` every single tick.
+    pub vision_command: Option<VisionMgmtCommand>,
+    pub frame_sleep_ms: u64,
+    pub hard_stop: bool,
+}
+
+/// Tracks the current throttle level across ticks and applies hysteresis.
+pub struct ThermalGovernor {
+    thresholds: ThermalThresholds,
+    level: ThermalLevel,
+}
+
+impl ThermalGovernor {
+    pub fn new(thresholds: ThermalThresholds) -> Self {
+        Self {
+            thresholds,
+            level: ThermalLevel::Nominal,
+        }
+    }
+
+    /// Current throttle level, e.g. to surface in state/telemetry.
+    pub fn level(&self) -> ThermalLevel {
+        self.level
+    }
+
+    /// Feeds the latest `pi_temp` reading and returns the action to apply.
+    pub fn assess(&mut self, pi_temp: f32) -> GovernorAction {
+        let t = &self.thresholds;
+        let raw = if pi_temp >= t.critical_c {
+            ThermalLevel::Critical
+        } else if pi_temp >= t.throttle_c {
+            ThermalLevel::Throttled
+        } else if pi_temp >= t.downshift_c {
+            ThermalLevel::Downshifted
+        } else {
+            ThermalLevel::Nominal
+        };
+
+        // Only ease back off the current level once the temperature has
+        // dropped `hysteresis_c` below the threshold that raised it, so the
+        // governor doesn't flap right at a boundary.
+        let eased = if pi_temp >= t.critical_c - t.hysteresis_c {
+            ThermalLevel::Critical
+        } else if pi_temp >= t.throttle_c - t.hysteresis_c {
+            ThermalLevel::Throttled
+        } else if pi_temp >= t.downshift_c - t.hysteresis_c {
+            ThermalLevel::Downshifted
+        } else {
+            ThermalLevel::Nominal
+        };
+        let next = if raw >= self.level {
+            raw
+        } else {
+            self.level.min(eased)
+        };
+
+        let changed = next != self.level;
+        self.level = next;
+
+        let vision_command = changed.then(|| match self.level {
+            ThermalLevel::Nominal => VisionMgmtCommand::SwitchSz640,
+            _ => VisionMgmtCommand::SwitchSz320,
+        });
+        let frame_sleep_ms = match self.level {
+            ThermalLevel::Nominal | ThermalLevel::Downshifted => NOMINAL_FRAME_SLEEP_MS,
+            ThermalLevel::Throttled | ThermalLevel::Critical => t.throttled_frame_sleep_ms,
+        };
+
+        GovernorAction {
+            level: self.level,
+            vision_command,
+            frame_sleep_ms,
+            hard_stop: self.level == ThermalLevel::Critical,
+        }
+    }
+}
+
+/// Feeds `pi_temp` into `governor` and resends the vision commands the
+/// resulting action implies, returning the action so the caller can still
+/// read `level` (to surface in state/telemetry) and `hard_stop` (to fold
+/// into its own system-risk check). Every pilot that runs a
+/// `ThermalGovernor` does exactly this each tick, so it lives here once
+/// instead of being copy-pasted into each pilot's `handle`.
+pub fn apply(
+    governor: &mut ThermalGovernor,
+    pi_temp: f32,
+    tx: &Sender<VisionMgmtCommand>,
+) -> GovernorAction {
+    let action = governor.assess(pi_temp);
+    // frame_sleep_ms is derived solely from the governor's level, so it
+    // only needs resending when vision_command does (i.e. the level just
+    // changed); otherwise this would flood the vision channel every tick.
+    if let Some(cmd) = action.vision_command {
+        let _ = tx.send(cmd);
+        let _ = tx.send(VisionMgmtCommand::SetFrameSleep(action.frame_sleep_ms));
+    }
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_nominal_with_no_level_change_and_no_hard_stop() {
+        let mut gov = ThermalGovernor::new(ThermalThresholds::default());
+        let action = gov.assess(20.0);
+        assert_eq!(action.level, ThermalLevel::Nominal);
+        assert_eq!(action.frame_sleep_ms, NOMINAL_FRAME_SLEEP_MS);
+        assert!(action.vision_command.is_none());
+        assert!(!action.hard_stop);
+    }
+
+    #[test]
+    fn crosses_each_threshold_upward_in_order() {
+        let mut gov = ThermalGovernor::new(ThermalThresholds::default());
+        gov.assess(20.0); // Nominal
+
+        let action = gov.assess(61.0); // >= downshift_c
+        assert_eq!(action.level, ThermalLevel::Downshifted);
+        assert!(matches!(action.vision_command, Some(VisionMgmtCommand::SwitchSz320)));
+        assert_eq!(action.frame_sleep_ms, NOMINAL_FRAME_SLEEP_MS);
+        assert!(!action.hard_stop);
+
+        let action = gov.assess(66.0); // >= throttle_c
+        assert_eq!(action.level, ThermalLevel::Throttled);
+        assert!(matches!(action.vision_command, Some(VisionMgmtCommand::SwitchSz320)));
+        assert_eq!(
+            action.frame_sleep_ms,
+            ThermalThresholds::default().throttled_frame_sleep_ms
+        );
+        assert!(!action.hard_stop);
+
+        let action = gov.assess(71.0); // >= critical_c
+        assert_eq!(action.level, ThermalLevel::Critical);
+        assert!(matches!(action.vision_command, Some(VisionMgmtCommand::SwitchSz320)));
+        assert_eq!(
+            action.frame_sleep_ms,
+            ThermalThresholds::default().throttled_frame_sleep_ms
+        );
+        assert!(action.hard_stop);
+    }
+
+    #[test]
+    fn repeated_assess_at_the_same_level_reports_no_further_vision_command() {
+        let mut gov = ThermalGovernor::new(ThermalThresholds::default());
+        gov.assess(61.0); // Downshifted, vision_command = Some(SwitchSz320)
+        let action = gov.assess(62.0); // still Downshifted, no change
+        assert_eq!(action.level, ThermalLevel::Downshifted);
+        assert!(action.vision_command.is_none());
+    }
+
+    #[test]
+    fn hysteresis_blocks_downgrade_until_the_margin_is_cleared() {
+        let mut gov = ThermalGovernor::new(ThermalThresholds::default());
+        gov.assess(66.0); // Throttled
+
+        // Below throttle_c (65.0) but still within the 3.0 hysteresis band
+        // (i.e. >= 62.0): must hold at Throttled rather than downgrade.
+        let action = gov.assess(64.0);
+        assert_eq!(action.level, ThermalLevel::Throttled);
+        assert!(action.vision_command.is_none());
+
+        let action = gov.assess(62.5);
+        assert_eq!(action.level, ThermalLevel::Throttled);
+        assert!(action.vision_command.is_none());
+
+        // Finally below the hysteresis margin (< 62.0): downgrades.
+        let action = gov.assess(61.0);
+        assert_eq!(action.level, ThermalLevel::Downshifted);
+        assert!(matches!(action.vision_command, Some(VisionMgmtCommand::SwitchSz320)));
+    }
+
+    #[test]
+    fn apply_resends_vision_commands_only_when_the_level_changes() {
+        let mut gov = ThermalGovernor::new(ThermalThresholds::default());
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let action = apply(&mut gov, 61.0, &tx); // Downshifted
+        assert_eq!(action.level, ThermalLevel::Downshifted);
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(VisionMgmtCommand::SwitchSz320)
+        ));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(VisionMgmtCommand::SetFrameSleep(_))
+        ));
+        assert!(rx.try_recv().is_err());
+
+        apply(&mut gov, 62.0, &tx); // still Downshifted, no change
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn downgrading_all_the_way_to_nominal_switches_back_to_640() {
+        let mut gov = ThermalGovernor::new(ThermalThresholds::default());
+        gov.assess(61.0); // Downshifted
+
+        // Below downshift_c - hysteresis_c (57.0): downgrades to Nominal.
+        let action = gov.assess(56.0);
+        assert_eq!(action.level, ThermalLevel::Nominal);
+        assert!(matches!(action.vision_command, Some(VisionMgmtCommand::SwitchSz640)));
+        assert_eq!(action.frame_sleep_ms, NOMINAL_FRAME_SLEEP_MS);
+    }
+}
@@ -15,10 +15,23 @@ use std::{
 use self::detector::Detection;
 // Import the RoktrackProperty type from the init submodule in the util module
 use super::util::init::RoktrackProperty;
+// Import the supervision tree that restarts this thread on panic
+use super::supervisor::Supervisor;
 
 pub mod camera; // Declare the camera submodule
 pub mod detector; // Declare the detector submodule
 
+/// Child id this thread registers under in the vision `Supervisor`.
+const SUPERVISOR_CHILD_ID: &str = "vision.run";
+/// Group tag this thread registers under in the vision `Supervisor`.
+const SUPERVISOR_GROUP: &str = "vision";
+/// Give up restarting the vision loop after this many panics inside the rolling window.
+const MAX_RESTARTS: u32 = 5;
+/// Rolling window over which `MAX_RESTARTS` is counted.
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+/// Default inter-frame sleep, matching the loop's original fixed interval.
+const DEFAULT_FRAME_SLEEP_MS: u64 = 10;
+
 /// This enum defines the commands that can be used to control the vision thread.
 pub enum VisionMgmtCommand {
     On,                    // Turn on the vision thread
@@ -28,6 +41,7 @@ pub enum VisionMgmtCommand {
     SwitchSessionAnimal,   // Switch to the animal detection session
     SwitchSz320,           // Switch to the 320x240 resolution
     SwitchSz640,           // Switch to the 640x480 resolution
+    SetFrameSleep(u64),    // Set the inter-frame sleep (ms), e.g. from the thermal governor
 }
 
 /// This struct provides a means of image processing using a camera and a detector.
@@ -35,6 +49,8 @@ pub struct RoktrackVision {
     inner: Arc<Mutex<RoktrackVisionInner>>, // A shared and synchronized wrapper for the inner struct that contains the camera and detector fields
     property: Arc<RoktrackProperty>, // A shared wrapper for the property struct that contains the paths and configurations
     state: Arc<Mutex<bool>>,
+    supervisor: Supervisor, // Restarts the inference loop with backoff if it panics, and tracks its health
+    frame_sleep_ms: Arc<Mutex<u64>>, // Inter-frame sleep, adjustable at runtime (e.g. by the thermal governor)
 }
 
 /// This impl block defines the methods for the RoktrackVision struct.
@@ -47,12 +63,31 @@ impl RoktrackVision {
             // Create a new Arc<RoktrackProperty> by calling the new method on the Arc type and passing the property
             property: Arc::new(property),
             state: Arc::new(Mutex::new(true)),
+            supervisor: Supervisor::new(MAX_RESTARTS, RESTART_WINDOW),
+            frame_sleep_ms: Arc::new(Mutex::new(DEFAULT_FRAME_SLEEP_MS)),
         }
     }
 
-    /// This method spawns a new thread that runs the inference loop for image processing.
-    /// It takes two arguments: a sender and a receiver for communicating with other threads.
-    /// It returns a handle to the spawned thread.
+    /// Returns a handle to this vision thread's supervisor, e.g. to report its
+    /// health registry (last heartbeat, restart count, liveness) to telemetry.
+    pub fn supervisor(&self) -> Supervisor {
+        self.supervisor.clone()
+    }
+
+    /// Returns the detector's currently active session type (Pylon, Pylon
+    /// OCR, or Animal at a given resolution), e.g. for the inspector console
+    /// to report alongside live telemetry.
+    pub fn session_type(&self) -> detector::onnx::SessionType {
+        self.inner.lock().unwrap().det.session_type.clone()
+    }
+
+    /// This method spawns a new, supervised thread that runs the inference loop
+    /// for image processing. It takes two arguments: a sender and a receiver
+    /// for communicating with other threads. It returns a handle to the
+    /// supervisor thread, which keeps the inference loop alive by restarting
+    /// it with exponential backoff whenever it panics, rather than letting a
+    /// single failure (a dropped frame, a failed inference) kill vision for
+    /// good. See `Supervisor` for the restart/escalation policy.
     ///
     /// # Note: THIS THREAD IS SLOW LOOP.
     pub fn run(
@@ -63,14 +98,21 @@ impl RoktrackVision {
         let local_self = self.inner.clone(); // Clone the inner field to avoid borrowing issues
         let local_property = self.property.clone(); // Clone the property field to avoid borrowing issues
         let local_state = self.state.clone();
+        let rx = Arc::new(Mutex::new(rx)); // Shared so the supervised body closure can be restarted (re-run) without consuming the receiver
+        let supervisor = self.supervisor.clone();
+        let local_frame_sleep = self.frame_sleep_ms.clone();
 
-        // Spawn a new thread and run an infinite loop
-        thread::spawn(move || loop {
-            // Wait for a short time before repeating the loop
-            thread::sleep(Duration::from_millis(10));
+        // The supervisor runs this body on its own thread and restarts it with
+        // backoff if it panics; the body itself still loops forever as before.
+        let body = move || loop {
+            // Wait for a short time before repeating the loop; adjustable at
+            // runtime (e.g. widened by the thermal governor under load).
+            thread::sleep(Duration::from_millis(
+                *local_frame_sleep.lock().unwrap(),
+            ));
 
             // Read the management commands from the receiver and match them
-            match rx.try_recv() {
+            match rx.lock().unwrap().try_recv() {
                 Ok(VisionMgmtCommand::Off) => {
                     *local_state.lock().unwrap() = false;
                     continue; // If the command is Off, skip the rest of the loop and try again
@@ -104,6 +146,10 @@ impl RoktrackVision {
                     local_self.lock().unwrap().det.session_type =
                         detector::onnx::SessionType::Sz640;
                 }
+                Ok(VisionMgmtCommand::SetFrameSleep(ms)) => {
+                    log::debug!("Vision VisionMgmtCommand::SetFrameSleep({ms}) Received");
+                    *local_frame_sleep.lock().unwrap() = ms;
+                }
                 Err(_) => {} // If there is no command or an error, do nothing and proceed
             }
 
@@ -141,23 +187,40 @@ impl RoktrackVision {
                     // Handle ocr
                     let ocr_support = local_self.lock().unwrap().det.support_ocr();
                     if ocr_support {
-                        dets = local_self
-                            .lock()
-                            .unwrap()
-                            .det
-                            .ocr(
-                                &local_property.path.img.last,
-                                dets.clone(),
-                                local_property.as_ref().clone(),
-                            )
-                            .unwrap();
+                        // Drop the `inner` guard before unwrapping: a transient OCR
+                        // failure must not poison the mutex, or every subsequent
+                        // restart re-panics on the very next lock and burns through
+                        // `MAX_RESTARTS` instead of recovering.
+                        let ocr_result = local_self.lock().unwrap().det.ocr(
+                            &local_property.path.img.last,
+                            dets.clone(),
+                            local_property.as_ref().clone(),
+                        );
+                        dets = ocr_result.unwrap();
                         log::debug!("Vision Detected With Ocr: {:?}", dets.clone());
                     }
                     visual_info.detections = dets;
                     tx.send(visual_info).unwrap(); // Send the detection results to other threads using the sender
                 }
             }
-        })
+
+            // Signal to the supervisor that this iteration completed without panicking
+            supervisor.heartbeat(SUPERVISOR_CHILD_ID);
+        };
+
+        // Escalation: vision doesn't own the device, so the strongest action it
+        // can take on its own is to stop processing frames. A pilot wired up via
+        // `FollowPerson::with_vision_supervisor` / `MonitorAnimal::with_vision_supervisor`
+        // (given this vision's `supervisor()` handle) notices `Supervisor::any_escalated`
+        // on its next tick and applies `base::stop` / `device.speak("vision_escalated")` itself.
+        let escalate_state = self.state.clone();
+        let on_escalate = move || {
+            *escalate_state.lock().unwrap() = false;
+            log::error!("Vision subsystem escalated after repeated panics; vision forced Off.");
+        };
+
+        self.supervisor
+            .supervise(SUPERVISOR_GROUP, SUPERVISOR_CHILD_ID, body, on_escalate)
     }
 }
 